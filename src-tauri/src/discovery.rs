@@ -0,0 +1,220 @@
+//! Discovery of a Python interpreter to run the app's embedded Python code
+//! against, for the cases where no `.venv` is active.
+
+use std::{
+    env::var,
+    fmt, fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use tracing::{debug, instrument, trace};
+
+/// Environment variable used to explicitly point at a system interpreter,
+/// bypassing venv/PATH discovery entirely.
+pub const OVERRIDE_ENV_VAR: &str = "LOCAL_TRANSLATE_PYTHON";
+
+/// Environment variable used to select a [`DiscoveryPolicy`] other than the
+/// default, e.g. `LOCAL_TRANSLATE_PYTHON_DISCOVERY=allowed`.
+pub const POLICY_ENV_VAR: &str = "LOCAL_TRANSLATE_PYTHON_DISCOVERY";
+
+/// Candidate interpreter names searched for on `PATH`, in order.
+const PATH_CANDIDATES: &[&str] = &["python3.13", "python3.12", "python3.11", "python3", "python"];
+
+/// How willing the build is to fall back to a system interpreter when no
+/// venv is active.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DiscoveryPolicy {
+    /// Only honor [`OVERRIDE_ENV_VAR`]; never search `PATH`. This is the
+    /// default, since silently picking up an arbitrary system Python is
+    /// surprising.
+    #[default]
+    Explicit,
+    /// Honor [`OVERRIDE_ENV_VAR`], and search `PATH` when no venv is active.
+    Allowed,
+    /// Never use a system interpreter, even via [`OVERRIDE_ENV_VAR`]; a venv
+    /// must be active.
+    Disallowed,
+}
+
+/// [`POLICY_ENV_VAR`] held a value other than `explicit`, `allowed`, or
+/// `disallowed`.
+#[derive(Debug)]
+pub struct ParsePolicyError(String);
+
+impl fmt::Display for ParsePolicyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid {POLICY_ENV_VAR} value `{}` (expected `explicit`, `allowed`, or `disallowed`)",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParsePolicyError {}
+
+impl FromStr for DiscoveryPolicy {
+    type Err = ParsePolicyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "explicit" => Ok(DiscoveryPolicy::Explicit),
+            "allowed" => Ok(DiscoveryPolicy::Allowed),
+            "disallowed" => Ok(DiscoveryPolicy::Disallowed),
+            _ => Err(ParsePolicyError(s.to_owned())),
+        }
+    }
+}
+
+/// Reads [`POLICY_ENV_VAR`], falling back to [`DiscoveryPolicy::default`]
+/// when it's unset.
+pub fn policy_from_env() -> Result<DiscoveryPolicy, ParsePolicyError> {
+    match var(POLICY_ENV_VAR) {
+        Ok(value) => value.parse(),
+        Err(_) => Ok(DiscoveryPolicy::default()),
+    }
+}
+
+/// Where the resolved interpreter came from.
+#[derive(Debug, Clone)]
+pub enum InterpreterSource {
+    /// Explicitly pointed at via [`OVERRIDE_ENV_VAR`].
+    ProvidedPath(PathBuf),
+    /// An active (or default-location) virtual environment.
+    ActiveVenv(PathBuf),
+    /// Found by searching `PATH`.
+    DiscoveredOnPath(PathBuf),
+}
+
+impl InterpreterSource {
+    /// A short label describing where this source came from, suitable for
+    /// logging (e.g. `from LOCAL_TRANSLATE_PYTHON`).
+    pub fn label(&self) -> &'static str {
+        match self {
+            InterpreterSource::ProvidedPath(_) => "from LOCAL_TRANSLATE_PYTHON",
+            InterpreterSource::ActiveVenv(_) => "from active venv",
+            InterpreterSource::DiscoveredOnPath(_) => "discovered on PATH",
+        }
+    }
+}
+
+/// Errors that can occur while discovering an interpreter.
+#[derive(Debug)]
+pub enum DiscoveryError {
+    /// [`OVERRIDE_ENV_VAR`] was set but does not point at a usable
+    /// interpreter.
+    InvalidOverride { path: PathBuf },
+    /// No venv is active and the policy forbids falling back to a system
+    /// interpreter.
+    VenvRequired,
+    /// No venv is active, the policy allows a system interpreter, but none
+    /// of [`PATH_CANDIDATES`] could be found on `PATH`.
+    NoSystemInterpreterFound,
+}
+
+impl fmt::Display for DiscoveryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiscoveryError::InvalidOverride { path } => write!(
+                f,
+                "{OVERRIDE_ENV_VAR} points at `{}`, which is not a usable interpreter",
+                path.display()
+            ),
+            DiscoveryError::VenvRequired => write!(
+                f,
+                "no virtual environment found and system interpreter discovery is disallowed"
+            ),
+            DiscoveryError::NoSystemInterpreterFound => write!(
+                f,
+                "no virtual environment found and no system Python interpreter could be found on PATH"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DiscoveryError {}
+
+/// Resolves which interpreter to use: the one named by [`OVERRIDE_ENV_VAR`]
+/// when it is set and `policy` is not `Disallowed` (even if `venv_dir`
+/// exists), else `venv_dir` when it exists, otherwise a system interpreter
+/// per `policy`.
+#[instrument(skip_all, fields(venv_dir = %venv_dir.display(), ?policy))]
+pub fn discover(policy: DiscoveryPolicy, venv_dir: &Path) -> Result<InterpreterSource, DiscoveryError> {
+    let override_path = var(OVERRIDE_ENV_VAR).ok().map(PathBuf::from);
+
+    if policy != DiscoveryPolicy::Disallowed {
+        if let Some(path) = override_path {
+            return if is_usable_interpreter(&path) {
+                debug!(path = %path.display(), "using interpreter from LOCAL_TRANSLATE_PYTHON");
+                Ok(InterpreterSource::ProvidedPath(path))
+            } else {
+                Err(DiscoveryError::InvalidOverride { path })
+            };
+        }
+    }
+
+    if venv_dir.exists() {
+        debug!(venv_dir = %venv_dir.display(), "using active venv");
+        return Ok(InterpreterSource::ActiveVenv(venv_dir.to_owned()));
+    }
+
+    match policy {
+        DiscoveryPolicy::Allowed => {
+            debug!("no venv active, searching PATH for a system interpreter");
+            search_path().map(InterpreterSource::DiscoveredOnPath)
+        }
+        DiscoveryPolicy::Explicit | DiscoveryPolicy::Disallowed => Err(DiscoveryError::VenvRequired),
+    }
+}
+
+/// Searches `PATH` for the first of [`PATH_CANDIDATES`] that resolves to a
+/// usable interpreter.
+#[instrument(skip_all)]
+fn search_path() -> Result<PathBuf, DiscoveryError> {
+    let path_var = var("PATH").unwrap_or_default();
+    let dirs: Vec<&str> = if cfg!(windows) {
+        path_var.split(';').collect()
+    } else {
+        path_var.split(':').collect()
+    };
+
+    for dir in dirs {
+        for candidate in PATH_CANDIDATES {
+            let name = if cfg!(windows) {
+                format!("{candidate}.exe")
+            } else {
+                (*candidate).to_owned()
+            };
+            let path = Path::new(dir).join(name);
+            trace!(candidate = %path.display(), "inspecting candidate");
+            if is_usable_interpreter(&path) {
+                debug!(path = %path.display(), "found system interpreter on PATH");
+                return Ok(path);
+            }
+        }
+    }
+
+    Err(DiscoveryError::NoSystemInterpreterFound)
+}
+
+/// Checks that `path` exists, is a file, and (on Unix) is executable.
+fn is_usable_interpreter(path: &Path) -> bool {
+    let Ok(metadata) = fs::metadata(path) else {
+        return false;
+    };
+    if !metadata.is_file() {
+        return false;
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        metadata.permissions().mode() & 0o111 != 0
+    }
+
+    #[cfg(not(unix))]
+    {
+        true
+    }
+}
@@ -1,5 +1,9 @@
 use pyo3::prelude::*;
 
+pub mod discovery;
+pub mod probe;
+pub mod venv;
+
 pub fn tauri_generate_context() -> tauri::Context {
     tauri::generate_context!()
 }
@@ -1,66 +1,217 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use std::{convert::Infallible, env::var, error::Error, path::PathBuf};
+use std::{
+    convert::Infallible,
+    env::var,
+    error::Error,
+    path::{Path, PathBuf},
+};
 
 use pyo3::wrap_pymodule;
 use pytauri::standalone::{
     dunce::simplified, PythonInterpreterBuilder, PythonInterpreterEnv, PythonScript,
 };
 use tauri::utils::platform::resource_dir;
+use tracing::{debug, info_span, trace};
+use tracing_subscriber::EnvFilter;
 
-use local_translate_lib::{ext_mod, tauri_generate_context};
-
-/// Find the site-packages directory inside a venv (e.g. `.venv/lib/python3.12/site-packages`).
-fn find_site_packages(venv_dir: &std::path::Path) -> Option<PathBuf> {
-    let lib_dir = venv_dir.join("lib");
-    if let Ok(entries) = std::fs::read_dir(&lib_dir) {
-        for entry in entries.flatten() {
-            let name = entry.file_name();
-            if name.to_string_lossy().starts_with("python") {
-                let sp = entry.path().join("site-packages");
-                if sp.exists() {
-                    return Some(sp);
-                }
-            }
+use local_translate_lib::{
+    discovery::{self, InterpreterSource},
+    ext_mod,
+    probe::{probe_interpreter, InterpreterProbe},
+    tauri_generate_context,
+    venv::VirtualEnvironment,
+};
+
+/// Env var pointing at an auxiliary directory of Python packages to layer
+/// on top of the resolved interpreter's own site-packages, e.g. to swap in
+/// an updated translation backend without rebuilding the bundle.
+const TARGET_ENV_VAR: &str = "LOCAL_TRANSLATE_TARGET";
+
+/// Oldest Python this app is tested against. Running against anything
+/// older risks confusing import errors or segfaults deep inside PyO3
+/// rather than a clear error at startup.
+const MINIMUM_SUPPORTED_VERSION: (u32, u32) = (3, 12);
+
+/// The compiled Rust target's pointer width, to compare against a
+/// candidate interpreter's own `struct.calcsize("P") * 8`. `CARGO_CFG_*`
+/// vars are only populated for build scripts, not for the crate itself, so
+/// this has to come from `cfg!` instead of `env!("CARGO_CFG_...")`.
+const TARGET_POINTER_WIDTH: u32 = if cfg!(target_pointer_width = "64") {
+    64
+} else {
+    32
+};
+
+/// Fails with a descriptive error if `probe` doesn't satisfy
+/// [`MINIMUM_SUPPORTED_VERSION`] or doesn't match [`TARGET_POINTER_WIDTH`].
+fn validate_interpreter(probe: &InterpreterProbe) -> Result<(), Box<dyn Error>> {
+    let (major, minor) = probe.version;
+    debug!(
+        version = format!("{major}.{minor}"),
+        implementation = probe.implementation,
+        pointer_width = probe.pointer_width,
+        "validating interpreter before embedding"
+    );
+
+    if probe.version < MINIMUM_SUPPORTED_VERSION {
+        let (min_major, min_minor) = MINIMUM_SUPPORTED_VERSION;
+        return Err(format!(
+            "configured interpreter {major}.{minor} is below minimum supported {min_major}.{min_minor}"
+        )
+        .into());
+    }
+
+    if probe.pointer_width != TARGET_POINTER_WIDTH {
+        return Err(format!(
+            "{TARGET_POINTER_WIDTH}-bit build found a {}-bit interpreter",
+            probe.pointer_width
+        )
+        .into());
+    }
+
+    Ok(())
+}
+
+/// Locates the bundled interpreter's own launcher inside a standalone
+/// resource dir, so it can be probed/validated before being embedded, the
+/// same as the dev-mode venv and system interpreters.
+fn standalone_python_executable(resource_dir: &Path) -> PathBuf {
+    let candidates = if cfg!(windows) {
+        [resource_dir.join("python.exe"), resource_dir.join("install").join("python.exe")]
+    } else {
+        [
+            resource_dir.join("bin").join("python3"),
+            resource_dir.join("install").join("bin").join("python3"),
+        ]
+    };
+    candidates
+        .iter()
+        .find(|path| path.exists())
+        .cloned()
+        .unwrap_or_else(|| candidates[0].clone())
+}
+
+/// Reads the `--target <dir>` / `--target=<dir>` CLI arg, falling back to
+/// [`TARGET_ENV_VAR`] when absent.
+fn target_dir() -> Option<PathBuf> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if let Some(value) = arg.strip_prefix("--target=") {
+            return Some(PathBuf::from(value));
+        }
+        if arg == "--target" {
+            return args.next().map(PathBuf::from);
         }
     }
-    None
+    var(TARGET_ENV_VAR).ok().map(PathBuf::from)
 }
 
 fn main() -> Result<Infallible, Box<dyn Error>> {
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::from_default_env())
+        .init();
+
+    let target_dir = target_dir();
+
     let py_env = if cfg!(dev) {
-        let venv_dir = var("VIRTUAL_ENV").map(PathBuf::from).unwrap_or_else(|_| {
-            let project_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-                .parent()
-                .expect("src-tauri should have a parent directory")
-                .to_owned();
-            project_root.join(".venv")
-        });
-        if !venv_dir.exists() {
-            return Err(format!(
-                "No virtual environment found at {}. Run `uv sync` first.",
-                venv_dir.display()
-            )
-            .into());
-        }
+        let span = info_span!("resolve_python_environment");
+        let _enter = span.enter();
+
+        let venv_dir = match var("VIRTUAL_ENV") {
+            Ok(v) => {
+                debug!(venv_dir = %v, "using venv from VIRTUAL_ENV");
+                PathBuf::from(v)
+            }
+            Err(_) => {
+                let project_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                    .parent()
+                    .expect("src-tauri should have a parent directory")
+                    .to_owned();
+                let venv_dir = project_root.join(".venv");
+                debug!(venv_dir = %venv_dir.display(), "VIRTUAL_ENV not set, falling back to .venv in the parent of CARGO_MANIFEST_DIR");
+                venv_dir
+            }
+        };
+
+        let policy = discovery::policy_from_env()?;
+        debug!(?policy, "resolved discovery policy");
+        let source = discovery::discover(policy, &venv_dir)?;
+        debug!(source = source.label(), "resolved interpreter source");
 
         // The embedded PyO3 interpreter doesn't process .pth files or
         // activate the venv (symlink resolution defeats pyvenv.cfg lookup).
         // Explicitly add our source dir and the venv's site-packages to PYTHONPATH.
         let src_python = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src-python");
         let mut paths = vec![src_python.to_string_lossy().into_owned()];
-        if let Some(sp) = find_site_packages(&venv_dir) {
-            paths.push(sp.to_string_lossy().into_owned());
+        if let Some(target_dir) = &target_dir {
+            debug!(target_dir = %target_dir.display(), "layering target directory onto PYTHONPATH");
+            paths.push(target_dir.to_string_lossy().into_owned());
+        }
+
+        let py_env = match source {
+            InterpreterSource::ActiveVenv(venv_dir) => {
+                let venv = VirtualEnvironment::from_dir(&venv_dir)
+                    .map_err(|err| format!("failed to read virtual environment: {err}"))?;
+                let (major, minor) = venv.version();
+                debug!(version = format!("{major}.{minor}"), "venv interpreter version");
+                paths.extend(
+                    venv.site_packages_directories()
+                        .map_err(|err| format!("failed to resolve venv site-packages: {err}"))?
+                        .into_iter()
+                        .map(|p| p.to_string_lossy().into_owned()),
+                );
+
+                let probe = probe_interpreter(&venv.python_executable())?;
+                validate_interpreter(&probe)?;
+
+                PythonInterpreterEnv::Venv(venv_dir.into())
+            }
+            InterpreterSource::ProvidedPath(executable)
+            | InterpreterSource::DiscoveredOnPath(executable) => {
+                let probe = probe_interpreter(&executable)?;
+                validate_interpreter(&probe)?;
+
+                debug!(
+                    prefix = %probe.prefix.display(),
+                    purelib = %probe.purelib.display(),
+                    "resolved system interpreter layout"
+                );
+                paths.push(probe.purelib.to_string_lossy().into_owned());
+
+                PythonInterpreterEnv::Standalone(probe.prefix.into())
+            }
+        };
+
+        for path in &paths {
+            trace!(entry = %path, "PYTHONPATH entry");
         }
-        std::env::set_var("PYTHONPATH", paths.join(":"));
+        let pythonpath = paths.join(if cfg!(windows) { ";" } else { ":" });
+        debug!(%pythonpath, "final PYTHONPATH");
+        std::env::set_var("PYTHONPATH", pythonpath);
 
-        PythonInterpreterEnv::Venv(venv_dir.into())
+        py_env
     } else {
+        if let Some(target_dir) = &target_dir {
+            let mut paths = vec![target_dir.to_string_lossy().into_owned()];
+            if let Ok(existing) = var("PYTHONPATH") {
+                paths.push(existing);
+            }
+            std::env::set_var(
+                "PYTHONPATH",
+                paths.join(if cfg!(windows) { ";" } else { ":" }),
+            );
+        }
+
         let context = tauri_generate_context();
         let resource_dir = resource_dir(context.package_info(), &tauri::Env::default())
             .map_err(|err| format!("failed to get resource dir: {err}"))?;
         let resource_dir = simplified(&resource_dir).to_owned();
+
+        let probe = probe_interpreter(&standalone_python_executable(&resource_dir))?;
+        validate_interpreter(&probe)?;
+
         PythonInterpreterEnv::Standalone(resource_dir.into())
     };
 
@@ -0,0 +1,120 @@
+//! Spawning a candidate Python interpreter to introspect its own version,
+//! architecture, and install layout before it's trusted (embedded via PyO3,
+//! or used to resolve another interpreter's site-packages).
+
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// Version, implementation and architecture reported by a candidate
+/// interpreter, along with its install layout.
+#[derive(Debug, Clone)]
+pub struct InterpreterProbe {
+    pub version: (u32, u32),
+    pub implementation: String,
+    pub pointer_width: u32,
+    /// `sys.prefix`, i.e. the root of this interpreter's install.
+    pub prefix: PathBuf,
+    /// `sysconfig.get_path("purelib")`: pure-Python `site-packages`.
+    pub purelib: PathBuf,
+    /// `sysconfig.get_path("platlib")`: platform-specific `site-packages`
+    /// (e.g. `lib64` on some distros); equal to `purelib` on most layouts.
+    pub platlib: PathBuf,
+}
+
+/// Errors that can occur while probing a candidate interpreter.
+#[derive(Debug)]
+pub enum ProbeError {
+    /// The interpreter could not be spawned at all (missing, not
+    /// executable, etc.).
+    Spawn { executable: PathBuf, reason: String },
+    /// The interpreter ran but exited non-zero.
+    NonZeroExit {
+        executable: PathBuf,
+        status: std::process::ExitStatus,
+    },
+    /// The interpreter's stdout didn't match the expected tab-separated
+    /// probe format.
+    UnexpectedOutput { executable: PathBuf, output: String },
+}
+
+impl fmt::Display for ProbeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProbeError::Spawn { executable, reason } => write!(
+                f,
+                "failed to run interpreter {}: {reason}",
+                executable.display()
+            ),
+            ProbeError::NonZeroExit { executable, status } => write!(
+                f,
+                "interpreter {} exited with {status} while probing its version",
+                executable.display()
+            ),
+            ProbeError::UnexpectedOutput { executable, output } => write!(
+                f,
+                "unexpected probe output from interpreter {}: {output:?}",
+                executable.display()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ProbeError {}
+
+/// Spawns `executable` as a subprocess to introspect its version,
+/// implementation, pointer width and install layout. Doing this in a
+/// subprocess, rather than trusting the venv/discovery layout conventions,
+/// means a mismatched or unusual interpreter is rejected with a clear error
+/// up front instead of surfacing as a confusing segfault or import error
+/// once it's already loaded in-process (or its site-packages guessed
+/// wrong).
+pub fn probe_interpreter(executable: &Path) -> Result<InterpreterProbe, ProbeError> {
+    let output = Command::new(executable)
+        .args([
+            "-c",
+            "import struct, sys, sysconfig; \
+             print('\\t'.join([str(sys.version_info.major), str(sys.version_info.minor), \
+             sys.implementation.name, str(struct.calcsize('P') * 8), \
+             sys.prefix, sysconfig.get_path('purelib'), sysconfig.get_path('platlib')]))",
+        ])
+        .output()
+        .map_err(|err| ProbeError::Spawn {
+            executable: executable.to_owned(),
+            reason: err.to_string(),
+        })?;
+
+    if !output.status.success() {
+        return Err(ProbeError::NonZeroExit {
+            executable: executable.to_owned(),
+            status: output.status,
+        });
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let unexpected = || ProbeError::UnexpectedOutput {
+        executable: executable.to_owned(),
+        output: stdout.to_string(),
+    };
+
+    let fields: Vec<&str> = stdout.trim().split('\t').collect();
+    let [major, minor, implementation, pointer_width, prefix, purelib, platlib] =
+        fields.as_slice()
+    else {
+        return Err(unexpected());
+    };
+
+    Ok(InterpreterProbe {
+        version: (
+            major.parse().map_err(|_| unexpected())?,
+            minor.parse().map_err(|_| unexpected())?,
+        ),
+        implementation: (*implementation).to_owned(),
+        pointer_width: pointer_width.parse().map_err(|_| unexpected())?,
+        prefix: PathBuf::from(prefix),
+        purelib: PathBuf::from(purelib),
+        platlib: PathBuf::from(platlib),
+    })
+}
@@ -0,0 +1,231 @@
+//! Parsing of `pyvenv.cfg` and resolution of a virtual environment's
+//! `site-packages` directories across platforms.
+
+use std::{
+    collections::HashMap,
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use tracing::{debug, instrument, trace};
+
+use crate::probe::{self, ProbeError};
+
+/// Errors that can occur while locating or parsing a virtual environment.
+#[derive(Debug)]
+pub enum VenvError {
+    /// `pyvenv.cfg` does not exist at the expected location.
+    MissingConfig(PathBuf),
+    /// `pyvenv.cfg` exists but is missing a required key or has an
+    /// unparseable value.
+    MalformedConfig { path: PathBuf, reason: String },
+    /// `include-system-site-packages = true`, but the base interpreter
+    /// (derived from `home`/`base-prefix`) could not be probed for its own
+    /// `purelib`/`platlib`.
+    BaseInterpreterProbeFailed {
+        executable: PathBuf,
+        source: ProbeError,
+    },
+}
+
+impl fmt::Display for VenvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VenvError::MissingConfig(path) => {
+                write!(f, "no pyvenv.cfg found at {}", path.display())
+            }
+            VenvError::MalformedConfig { path, reason } => {
+                write!(f, "malformed pyvenv.cfg at {}: {reason}", path.display())
+            }
+            VenvError::BaseInterpreterProbeFailed { executable, source } => write!(
+                f,
+                "failed to resolve base interpreter {} for include-system-site-packages: {source}",
+                executable.display()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for VenvError {}
+
+/// A parsed Python virtual environment, as described by its `pyvenv.cfg`.
+#[derive(Debug, Clone)]
+pub struct VirtualEnvironment {
+    /// Root directory of the venv (the directory containing `pyvenv.cfg`).
+    root: PathBuf,
+    /// `home` entry: directory holding the base interpreter's executable.
+    home: PathBuf,
+    /// `base-prefix` entry, if present (added in newer `venv`/`virtualenv`).
+    base_prefix: Option<PathBuf>,
+    /// `(major, minor)` parsed from `version` or `version_info`.
+    version: (u32, u32),
+    /// `include-system-site-packages` entry.
+    include_system_site_packages: bool,
+}
+
+impl VirtualEnvironment {
+    /// Reads and parses the `pyvenv.cfg` at the root of `venv_dir`.
+    #[instrument(skip_all, fields(venv_dir = %venv_dir.display()))]
+    pub fn from_dir(venv_dir: &Path) -> Result<Self, VenvError> {
+        let config_path = venv_dir.join("pyvenv.cfg");
+        let contents = fs::read_to_string(&config_path)
+            .map_err(|_| VenvError::MissingConfig(config_path.clone()))?;
+
+        let fields = parse_cfg(&contents);
+
+        let malformed = |reason: &str| VenvError::MalformedConfig {
+            path: config_path.clone(),
+            reason: reason.to_owned(),
+        };
+
+        let home = fields
+            .get("home")
+            .ok_or_else(|| malformed("missing `home` key"))?;
+        let home = PathBuf::from(home);
+
+        let version_str = fields
+            .get("version_info")
+            .or_else(|| fields.get("version"))
+            .ok_or_else(|| malformed("missing `version` or `version_info` key"))?;
+        let version = parse_version(version_str)
+            .ok_or_else(|| malformed(&format!("unparseable version `{version_str}`")))?;
+
+        let base_prefix = fields.get("base-prefix").map(PathBuf::from);
+
+        let include_system_site_packages = fields
+            .get("include-system-site-packages")
+            .map(|v| v.eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+
+        debug!(
+            version = format!("{}.{}", version.0, version.1),
+            include_system_site_packages, "parsed pyvenv.cfg"
+        );
+
+        Ok(Self {
+            root: venv_dir.to_owned(),
+            home,
+            base_prefix,
+            version,
+            include_system_site_packages,
+        })
+    }
+
+    /// The `(major, minor)` Python version this venv was created with.
+    pub fn version(&self) -> (u32, u32) {
+        self.version
+    }
+
+    /// Path to this venv's own Python launcher (as opposed to `home`, the
+    /// base interpreter it was created from), suitable for probing the
+    /// venv's interpreter before it's embedded.
+    pub fn python_executable(&self) -> PathBuf {
+        if cfg!(windows) {
+            self.root.join("Scripts").join("python.exe")
+        } else {
+            self.root.join("bin").join("python3")
+        }
+    }
+
+    /// All `site-packages` directories that should be put on `PYTHONPATH`
+    /// for this venv, in priority order: the venv's own `site-packages`
+    /// first, followed by the base interpreter's `purelib`/`platlib` when
+    /// `include-system-site-packages = true`.
+    #[instrument(skip_all, fields(venv_dir = %self.root.display()))]
+    pub fn site_packages_directories(&self) -> Result<Vec<PathBuf>, VenvError> {
+        let venv_site_packages = platform_site_packages(&self.root, self.version);
+        trace!(dir = %venv_site_packages.display(), "venv site-packages");
+        let mut dirs = vec![venv_site_packages];
+
+        if self.include_system_site_packages {
+            let base_executable = self.base_interpreter_executable();
+            let base_probe =
+                probe::probe_interpreter(&base_executable).map_err(|source| {
+                    VenvError::BaseInterpreterProbeFailed {
+                        executable: base_executable.clone(),
+                        source,
+                    }
+                })?;
+            trace!(
+                purelib = %base_probe.purelib.display(),
+                platlib = %base_probe.platlib.display(),
+                "base interpreter site-packages"
+            );
+            dirs.push(base_probe.purelib.clone());
+            if base_probe.platlib != base_probe.purelib {
+                dirs.push(base_probe.platlib);
+            }
+        }
+
+        Ok(dirs)
+    }
+
+    /// Best-effort path to the base interpreter's own launcher, derived
+    /// from `base-prefix` (preferred) or `home`, used to probe its
+    /// `sysconfig` layout rather than reconstructing it from the venv's own
+    /// conventions.
+    fn base_interpreter_executable(&self) -> PathBuf {
+        // `base-prefix` is the install prefix (e.g. `/usr`), so its `bin`
+        // subdir holds the launcher; `home` is conventionally already that
+        // `bin` directory (e.g. `/usr/bin`) per the pyvenv.cfg convention.
+        let bin_dir = match &self.base_prefix {
+            Some(base_prefix) if cfg!(windows) => base_prefix.clone(),
+            Some(base_prefix) => base_prefix.join("bin"),
+            None => self.home.clone(),
+        };
+
+        let names = [
+            format!("python{}.{}", self.version.0, self.version.1),
+            "python3".to_owned(),
+            "python".to_owned(),
+        ];
+        for name in names {
+            let candidate = bin_dir.join(if cfg!(windows) {
+                format!("{name}.exe")
+            } else {
+                name
+            });
+            if candidate.exists() {
+                return candidate;
+            }
+        }
+
+        bin_dir.join(if cfg!(windows) { "python.exe" } else { "python3" })
+    }
+}
+
+/// Builds the `site-packages` path for a Python install rooted at `root`,
+/// e.g. `lib/python3.12/site-packages` on Unix or `Lib/site-packages` on
+/// Windows.
+fn platform_site_packages(root: &Path, version: (u32, u32)) -> PathBuf {
+    if cfg!(windows) {
+        root.join("Lib").join("site-packages")
+    } else {
+        root.join("lib")
+            .join(format!("python{}.{}", version.0, version.1))
+            .join("site-packages")
+    }
+}
+
+/// Parses a flat `key = value` file, ignoring blank lines and `#` comments.
+fn parse_cfg(contents: &str) -> HashMap<String, String> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                return None;
+            }
+            let (key, value) = line.split_once('=')?;
+            Some((key.trim().to_owned(), value.trim().to_owned()))
+        })
+        .collect()
+}
+
+/// Parses a `major.minor[.patch[...]]` version string into `(major, minor)`.
+fn parse_version(version: &str) -> Option<(u32, u32)> {
+    let mut parts = version.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    Some((major, minor))
+}